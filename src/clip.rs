@@ -0,0 +1,208 @@
+use crate::raster::Coord;
+
+/// Guards against `w` being zero or negative when a vertex sits on
+/// or behind the camera.
+const NEAR_W_EPSILON: f32 = 1e-5;
+
+/// A vertex in clip space (i.e. before the perspective divide),
+/// carrying the attributes that need to be interpolated when an
+/// edge is clipped.
+#[derive(Clone, Copy)]
+pub struct ClipVertex {
+    pub position: [f32; 4],
+    pub colour: Option<crate::raster::Colour>,
+    pub tex_coord: Option<Coord>,
+    pub normal: Option<[f32; 3]>
+}
+
+/// A clip-space plane a triangle can be clipped against.
+pub enum Plane {
+    /// `w > epsilon`. Without this, triangles straddling the camera
+    /// produce wildly wrong screen coordinates after the perspective
+    /// divide, so this plane should always be clipped against.
+    NearW,
+    Left,
+    Right,
+    Bottom,
+    Top,
+    Near,
+    Far
+}
+
+impl Plane {
+    /// Signed distance of `v` from the plane; positive is "inside".
+    fn distance(&self, v: &ClipVertex) -> f32 {
+        let [x, y, z, w] = v.position;
+        match self {
+            Plane::NearW => w - NEAR_W_EPSILON,
+            Plane::Left => x + w,
+            Plane::Right => w - x,
+            Plane::Bottom => y + w,
+            Plane::Top => w - y,
+            Plane::Near => z + w,
+            Plane::Far => w - z
+        }
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Linearly interpolate a clip-space vertex and all its attributes
+/// between `a` and `b` at parameter `t`.
+fn lerp_vertex(a: &ClipVertex, b: &ClipVertex, t: f32) -> ClipVertex {
+    let position = [
+        lerp(a.position[0], b.position[0], t),
+        lerp(a.position[1], b.position[1], t),
+        lerp(a.position[2], b.position[2], t),
+        lerp(a.position[3], b.position[3], t)
+    ];
+    let colour = match (a.colour, b.colour) {
+        (Some(ca), Some(cb)) => Some(ca.lerp(&cb, t)),
+        _ => None
+    };
+    let tex_coord = match (a.tex_coord, b.tex_coord) {
+        (Some(ta), Some(tb)) => Some(Coord { x: lerp(ta.x, tb.x, t), y: lerp(ta.y, tb.y, t) }),
+        _ => None
+    };
+    let normal = match (a.normal, b.normal) {
+        (Some(na), Some(nb)) => Some([lerp(na[0], nb[0], t), lerp(na[1], nb[1], t), lerp(na[2], nb[2], t)]),
+        _ => None
+    };
+    ClipVertex { position, colour, tex_coord, normal }
+}
+
+/// Clip a convex polygon against a single plane (Sutherland-Hodgman).
+fn clip_against_plane(vertices: &[ClipVertex], plane: &Plane) -> Vec<ClipVertex> {
+    let n = vertices.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut out = Vec::with_capacity(n + 1);
+    for i in 0..n {
+        let curr = &vertices[i];
+        let prev = &vertices[(i + n - 1) % n];
+        let d_curr = plane.distance(curr);
+        let d_prev = plane.distance(prev);
+
+        if d_curr >= 0.0 {
+            if d_prev < 0.0 {
+                let t = d_prev / (d_prev - d_curr);
+                out.push(lerp_vertex(prev, curr, t));
+            }
+            out.push(*curr);
+        } else if d_prev >= 0.0 {
+            let t = d_prev / (d_prev - d_curr);
+            out.push(lerp_vertex(prev, curr, t));
+        }
+    }
+    out
+}
+
+/// Clip a triangle in clip space against `planes` in turn, returning
+/// the vertices of the resulting convex polygon (empty if the
+/// triangle lies entirely outside one of the planes).
+pub fn clip_triangle(triangle: [ClipVertex; 3], planes: &[Plane]) -> Vec<ClipVertex> {
+    let mut vertices = triangle.to_vec();
+    for plane in planes {
+        vertices = clip_against_plane(&vertices, plane);
+        if vertices.is_empty() {
+            break;
+        }
+    }
+    vertices
+}
+
+/// Fan-triangulate the convex polygon produced by `clip_triangle`
+/// back into triangles for the rasteriser.
+pub fn fan_triangulate(vertices: &[ClipVertex]) -> Vec<[ClipVertex; 3]> {
+    if vertices.len() < 3 {
+        return Vec::new();
+    }
+    (1..vertices.len() - 1).map(|i| [vertices[0], vertices[i], vertices[i + 1]]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertex(x: f32, y: f32, z: f32, w: f32) -> ClipVertex {
+        ClipVertex { position: [x, y, z, w], colour: None, tex_coord: None, normal: None }
+    }
+
+    #[test]
+    fn clip_triangle_fully_inside_is_unchanged() {
+        let triangle = [
+            vertex(-0.5, -0.5, 0.0, 1.0),
+            vertex(0.5, -0.5, 0.0, 1.0),
+            vertex(0.0, 0.5, 0.0, 1.0),
+        ];
+        let clipped = clip_triangle(triangle, &[Plane::Left, Plane::Right, Plane::Bottom, Plane::Top]);
+        assert_eq!(clipped.len(), 3);
+    }
+
+    #[test]
+    fn clip_triangle_fully_outside_is_empty() {
+        let triangle = [
+            vertex(2.0, 2.0, 0.0, 1.0),
+            vertex(3.0, 2.0, 0.0, 1.0),
+            vertex(2.0, 3.0, 0.0, 1.0),
+        ];
+        let clipped = clip_triangle(triangle, &[Plane::Right]);
+        assert!(clipped.is_empty());
+    }
+
+    #[test]
+    fn clip_triangle_straddling_plane_becomes_quad() {
+        // One vertex is past the right plane (x > w); clipping it off
+        // should leave a quad (two new vertices on the clip edge).
+        let triangle = [
+            vertex(-1.0, -1.0, 0.0, 1.0),
+            vertex(2.0, -1.0, 0.0, 1.0),
+            vertex(-1.0, 1.0, 0.0, 1.0),
+        ];
+        let clipped = clip_triangle(triangle, &[Plane::Right]);
+        assert_eq!(clipped.len(), 4);
+        for v in &clipped {
+            assert!(v.position[3] - v.position[0] >= -1e-5);
+        }
+    }
+
+    #[test]
+    fn clip_triangle_interpolates_attributes_at_new_vertices() {
+        let mut a = vertex(-1.0, -1.0, 0.0, 1.0);
+        a.colour = Some(crate::raster::Colour { r: 0, g: 0, b: 0 });
+        let mut b = vertex(2.0, -1.0, 0.0, 1.0);
+        b.colour = Some(crate::raster::Colour { r: 255, g: 255, b: 255 });
+        let mut c = vertex(-1.0, 1.0, 0.0, 1.0);
+        c.colour = Some(crate::raster::Colour { r: 0, g: 0, b: 0 });
+
+        let clipped = clip_triangle([a, b, c], &[Plane::Right]);
+        // New vertices produced on the clip edge should carry a
+        // colour strictly between the two it was interpolated from.
+        assert!(clipped.iter().any(|v| {
+            let r = v.colour.unwrap().r;
+            r > 0 && r < 255
+        }));
+    }
+
+    #[test]
+    fn fan_triangulate_quad_yields_two_triangles() {
+        let quad = vec![
+            vertex(0.0, 0.0, 0.0, 1.0),
+            vertex(1.0, 0.0, 0.0, 1.0),
+            vertex(1.0, 1.0, 0.0, 1.0),
+            vertex(0.0, 1.0, 0.0, 1.0),
+        ];
+        let triangles = fan_triangulate(&quad);
+        assert_eq!(triangles.len(), 2);
+    }
+
+    #[test]
+    fn fan_triangulate_degenerate_input_yields_nothing() {
+        assert!(fan_triangulate(&[]).is_empty());
+        assert!(fan_triangulate(&[vertex(0.0, 0.0, 0.0, 1.0), vertex(1.0, 0.0, 0.0, 1.0)]).is_empty());
+    }
+}
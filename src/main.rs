@@ -1,4 +1,7 @@
 mod raster;
+mod mesh;
+mod tessellate;
+mod clip;
 
 use raster::*;
 use winit::{
@@ -207,12 +210,12 @@ fn main() {
                 });
 
                 // TODO: raster
-                let data = &vec![
+                let data: &[Polygon] = &[
                     Polygon{
                         vertices: [
-                            Coord {x: 127.5, y: 32.0},
-                            Coord {x: 32.0, y: 224.0},
-                            Coord {x: 224.0, y: 224.0}
+                            Coord3::screen(127.5, 32.0),
+                            Coord3::screen(32.0, 224.0),
+                            Coord3::screen(224.0, 224.0)
                         ],
                         colours: Some([
                             Colour { r: 0xFF, g: 0, b: 0 },
@@ -223,10 +226,66 @@ fn main() {
                             Coord {x: 16.0, y: 0.0},
                             Coord {x: 0.0, y: 32.0},
                             Coord {x: 32.0, y: 32.0}
-                        ])
+                        ]),
+                        normals: None
                     }
                 ];
-                rasterise(buf.data, data, &Texture::checkerboard());
+                let sampler = Sampler { filter: Filter::Nearest, wrap: Wrap::Repeat };
+                rasterise(buf.data, data, &Texture::checkerboard(), &sampler, &Shading::None);
+
+                // A flat triangle lit with per-vertex (Gouraud) lighting.
+                let key_light = Light {
+                    direction: [0.3, -0.5, -0.8],
+                    colour: Colour::white(),
+                    ambient: 0.1
+                };
+                // Texture coordinates run outside [0, 32) on all three corners, so
+                // the mirrored wrap mode is actually exercised.
+                let mirror_sampler = Sampler { filter: Filter::Nearest, wrap: Wrap::Mirror };
+                let lit_data: &[Polygon] = &[
+                    Polygon{
+                        vertices: [
+                            Coord3::screen(127.5, 8.0),
+                            Coord3::screen(96.0, 64.0),
+                            Coord3::screen(159.0, 64.0)
+                        ],
+                        colours: Some([Colour::white(), Colour::white(), Colour::white()]),
+                        tex_coords: Some([
+                            Coord { x: 16.0, y: -16.0 },
+                            Coord { x: -16.0, y: 16.0 },
+                            Coord { x: 48.0, y: 16.0 }
+                        ]),
+                        normals: Some([[0.0, 0.0, 1.0], [-0.3, 0.2, 1.0], [0.3, 0.2, 1.0]])
+                    }
+                ];
+                rasterise(buf.data, lit_data, &Texture::checkerboard(), &mirror_sampler, &Shading::Gouraud(&key_light));
+
+                // Load a mesh from disk, light it per-fragment (Phong), and
+                // draw it in front of the camera. Sampled with bilinear
+                // filtering and clamped addressing, since the mesh's UVs
+                // stay within the texture's [0, 32] texel range.
+                let bilinear_sampler = Sampler { filter: Filter::Bilinear, wrap: Wrap::ClampToEdge };
+                let tetra = mesh::Mesh::load_obj("assets/tetra.obj");
+                // No camera movement yet, so the view transform is the identity.
+                let view = mesh::Mat4::identity();
+                // The camera looks down -Z, so the mesh needs a negative Z
+                // translation to land in front of it rather than behind.
+                let mvp = mesh::Mat4::perspective(0.1, 100.0).mul(&view).mul(&mesh::Mat4::translation(0.0, 0.0, -4.0));
+                let tetra_polygons = tetra.polygons(&mvp);
+                rasterise_parallel(buf.data, &tetra_polygons, &Texture::checkerboard(), &bilinear_sampler, &Shading::Phong(&key_light), 256);
+
+                // Tessellate and fill an L-shaped path (it has a collinear
+                // point along one edge, to exercise the boundary case).
+                let l_shape = tessellate::tessellate(&[
+                    Coord { x: 160.0, y: 160.0 },
+                    Coord { x: 224.0, y: 160.0 },
+                    Coord { x: 224.0, y: 192.0 },
+                    Coord { x: 224.0, y: 224.0 },
+                    Coord { x: 192.0, y: 224.0 },
+                    Coord { x: 192.0, y: 192.0 },
+                    Coord { x: 160.0, y: 192.0 },
+                ]);
+                rasterise(buf.data, &l_shape, &Texture::checkerboard(), &sampler, &Shading::None);
 
                 let tex_buffer = buf.finish();
 
@@ -5,6 +5,27 @@ pub struct Coord {
     pub y: f32
 }
 
+/// A vertex position carrying the depth and perspective
+/// components needed for correct 3D rasterisation.
+///
+/// `z` is the screen-space depth used for the depth test,
+/// and `w` is the clip-space w component (the perspective
+/// divisor) used to make attribute interpolation
+/// perspective-correct.
+#[derive(Clone, Copy)]
+pub struct Coord3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32
+}
+
+impl Coord3 {
+    pub fn screen(x: f32, y: f32) -> Self {
+        Coord3 { x, y, z: 0.0, w: 1.0 }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct Colour {
     pub r: u8,
@@ -27,43 +48,114 @@ impl Colour {
             b: ((self.b as u16 + other.b as u16) / 2) as u8
         }
     }
+
+    /// Linearly interpolate towards `other` by `t` (0.0 = self, 1.0 = other).
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Colour {
+            r: (self.r as f32 + (other.r as f32 - self.r as f32) * t) as u8,
+            g: (self.g as f32 + (other.g as f32 - self.g as f32) * t) as u8,
+            b: (self.b as f32 + (other.b as f32 - self.b as f32) * t) as u8
+        }
+    }
 }
 
 pub struct Polygon {
-    pub vertices: [Coord; 3],
+    pub vertices: [Coord3; 3],
     pub colours: Option<[Colour; 3]>,
-    pub tex_coords: Option<[Coord; 3]>
+    pub tex_coords: Option<[Coord; 3]>,
+    pub normals: Option<[[f32; 3]; 3]>
+}
+
+/// A single directional light.
+pub struct Light {
+    pub direction: [f32; 3],
+    pub colour: Colour,
+    pub ambient: f32
+}
+
+/// How a polygon's `normals` should be used to shade its fragments.
+pub enum Shading<'a> {
+    /// No lighting; draw colours/textures as-is.
+    None,
+    /// Compute the Lambert term at each vertex and interpolate the
+    /// resulting intensity across the triangle.
+    Gouraud(&'a Light),
+    /// Interpolate the normal across the triangle and compute the
+    /// Lambert term per-fragment.
+    Phong(&'a Light)
+}
+
+fn normalise(v: [f32; 3]) -> [f32; 3] {
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if len == 0.0 {
+        v
+    } else {
+        [v[0] / len, v[1] / len, v[2] / len]
+    }
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Lambertian diffuse intensity of `normal` under `light`, with
+/// `light.ambient` as a floor so unlit fragments aren't fully black.
+fn lambert(normal: [f32; 3], light: &Light) -> f32 {
+    let n = normalise(normal);
+    let to_light = normalise([-light.direction[0], -light.direction[1], -light.direction[2]]);
+    let diffuse = f32::max(0.0, dot(n, to_light));
+    light.ambient + (1.0 - light.ambient) * diffuse
+}
+
+/// Multiply `colour` by the light's colour and the shaded intensity.
+fn apply_light(colour: Colour, intensity: f32, light: &Light) -> Colour {
+    Colour {
+        r: (colour.r as f32 * intensity * (light.colour.r as f32 / 255.0)) as u8,
+        g: (colour.g as f32 * intensity * (light.colour.g as f32 / 255.0)) as u8,
+        b: (colour.b as f32 * intensity * (light.colour.b as f32 / 255.0)) as u8,
+    }
 }
 
 pub struct BoundingBox {
-    pub min: Coord,
-    pub max: Coord
+    pub min: Coord3,
+    pub max: Coord3
 }
 
 /// Find cross product of the vectors a->b and a->c.
 /// Since the Z component of both vectors is 0,
 /// the result only contains a Z component. So the
 /// result is effectively scalar.
-/// 
+///
 /// This serves two purposes:
-/// 
+///
 /// 1. If the result is positive, it means the point c is
-/// on the correct side of the line from a->b.
-/// This can be used to rasterise.
-/// 
+///    on the correct side of the line from a->b.
+///    This can be used to rasterise.
+///
 /// 2. The result also represents the area of the triangle
-/// between the three points, multiplied by 2.
-/// This can be used to find the weights of each point.
-fn edge_function(a: &Coord, b: &Coord, c: &Coord) -> f32 {
+///    between the three points, multiplied by 2.
+///    This can be used to find the weights of each point.
+fn edge_function(a: &Coord3, b: &Coord3, c: &Coord3) -> f32 {
     let x = (c.x - a.x) * (b.y - a.y);
     let y = (c.y - a.y) * (b.x - a.x);
     x - y
 }
 
-fn interpolate(vals: &[f32], parts: &[f32]) -> f32 {
+fn interpolate(vals: &[f32; 3], parts: &[f32; 3]) -> f32 {
     vals[0] * parts[0] + vals[1] * parts[1] + vals[2] * parts[2]
 }
 
+/// Interpolate a perspective-correct attribute.
+///
+/// `vals` are the raw per-vertex attribute values, `inv_w`
+/// is the per-vertex `1/w`, `weights` are the barycentric
+/// weights from `test_inside`, and `invw` is those weights
+/// applied to `inv_w` (the interpolated `1/w` at this point).
+fn interpolate_perspective(vals: &[f32; 3], inv_w: &[f32; 3], weights: &[f32; 3], invw: f32) -> f32 {
+    let weighted = [vals[0] * inv_w[0], vals[1] * inv_w[1], vals[2] * inv_w[2]];
+    interpolate(&weighted, weights) / invw
+}
+
 impl Polygon {
     fn bounding_box(&self) -> BoundingBox {
         let mut min = self.vertices[0];
@@ -77,7 +169,7 @@ impl Polygon {
         BoundingBox { min, max }
     }
 
-    fn test_inside(&self, coord: &Coord) -> Option<[f32; 3]> {
+    pub(crate) fn test_inside(&self, coord: &Coord3) -> Option<[f32; 3]> {
         let mut w = [0.0; 3];
         let area = edge_function(&self.vertices[0], &self.vertices[1], &self.vertices[2]);
         for i in 0..3 {
@@ -99,46 +191,203 @@ impl Polygon {
 
 }
 
+/// Rasterise `polygon` into `out`/`depth_buffer`, which cover a
+/// `surface_width`-wide region whose first row corresponds to
+/// absolute surface row `y_offset`. The scan is clipped to
+/// `x_range`/`y_range` (absolute surface coordinates), so callers
+/// can restrict work to a tile.
+///
+/// This is the shared inner loop used by both the serial
+/// `rasterise` and the tiled `rasterise_parallel`.
+#[allow(clippy::too_many_arguments)]
+fn rasterise_triangle(
+    out: &mut [u8],
+    depth_buffer: &mut [f32],
+    polygon: &Polygon,
+    texture: &Texture,
+    sampler: &Sampler,
+    shading: &Shading,
+    surface_width: usize,
+    x_range: std::ops::RangeInclusive<i32>,
+    y_range: std::ops::RangeInclusive<i32>,
+    y_offset: i32,
+) {
+    let inv_w = [
+        1.0 / polygon.vertices[0].w,
+        1.0 / polygon.vertices[1].w,
+        1.0 / polygon.vertices[2].w
+    ];
+    let z = [polygon.vertices[0].z, polygon.vertices[1].z, polygon.vertices[2].z];
+    // Precompute the per-vertex Lambert term for Gouraud shading, so
+    // it's calculated once per vertex for each call rather than per pixel.
+    let gouraud_intensity = match shading {
+        Shading::Gouraud(light) => polygon.normals.map(|normals| [
+            lambert(normals[0], light),
+            lambert(normals[1], light),
+            lambert(normals[2], light)
+        ]),
+        _ => None
+    };
+    for y in y_range {
+        for x in x_range.clone() {
+            let coord = Coord3::screen(x as f32, y as f32);
+            if let Some(interp) = polygon.test_inside(&coord) {
+                let local_y = (y - y_offset) as usize;
+                let depth_idx = (local_y * surface_width) + x as usize;
+                let depth = interpolate(&z, &interp);
+                if depth >= depth_buffer[depth_idx] {
+                    continue;
+                }
+
+                let invw = interpolate(&inv_w, &interp);
+
+                // Interpolate colour.
+                let shaded_colours = polygon.colours.map(|colours| {
+                    Colour {
+                        r: interpolate_perspective(&[colours[0].r as f32, colours[1].r as f32, colours[2].r as f32], &inv_w, &interp, invw) as u8,
+                        g: interpolate_perspective(&[colours[0].g as f32, colours[1].g as f32, colours[2].g as f32], &inv_w, &interp, invw) as u8,
+                        b: interpolate_perspective(&[colours[0].b as f32, colours[1].b as f32, colours[2].b as f32], &inv_w, &interp, invw) as u8,
+                    }
+                });
+                let tex_colours = polygon.tex_coords.map(|tex_coords| {
+                    let u = interpolate_perspective(&[tex_coords[0].x, tex_coords[1].x, tex_coords[2].x], &inv_w, &interp, invw);
+                    let v = interpolate_perspective(&[tex_coords[0].y, tex_coords[1].y, tex_coords[2].y], &inv_w, &interp, invw);
+                    texture.sample(u, v, sampler)
+                });
+                let blended_colour = match (shaded_colours, tex_colours) {
+                    (None, None) => Colour::black(),
+                    (Some(c), None) => c,
+                    (None, Some(c)) => c,
+                    (Some(a), Some(b)) => a.blend(&b),
+                };
+
+                let lit_colour = match shading {
+                    Shading::None => blended_colour,
+                    Shading::Gouraud(light) => match gouraud_intensity {
+                        Some(vertex_intensity) => apply_light(blended_colour, interpolate(&vertex_intensity, &interp), light),
+                        None => blended_colour,
+                    },
+                    Shading::Phong(light) => match polygon.normals {
+                        Some(normals) => {
+                            let normal = [
+                                interpolate(&[normals[0][0], normals[1][0], normals[2][0]], &interp),
+                                interpolate(&[normals[0][1], normals[1][1], normals[2][1]], &interp),
+                                interpolate(&[normals[0][2], normals[1][2], normals[2][2]], &interp),
+                            ];
+                            apply_light(blended_colour, lambert(normal, light), light)
+                        },
+                        None => blended_colour,
+                    },
+                };
+
+                depth_buffer[depth_idx] = depth;
+                let idx = ((local_y * surface_width) + x as usize) * 4;
+                out[idx] = lit_colour.r;
+                out[idx+1] = lit_colour.g;
+                out[idx+2] = lit_colour.b;
+            }
+        }
+    }
+}
+
 /// Rasterise to a 256x256 surface.
-/// 
+///
+/// Uses a per-pixel depth buffer so overlapping triangles are
+/// resolved nearest-first, and interpolates colour/texture
+/// attributes perspective-correctly using each vertex's `1/w`.
+///
 /// TODO: provide frag shader
-pub fn rasterise(out: &mut [u8], polygons: &[Polygon], texture: &Texture) {
+pub fn rasterise(out: &mut [u8], polygons: &[Polygon], texture: &Texture, sampler: &Sampler, shading: &Shading) {
+    let mut depth_buffer = vec![f32::INFINITY; 256 * 256];
     for polygon in polygons {
         let bounding_box = polygon.bounding_box();
-        for y in (bounding_box.min.y.floor() as i32)..=(bounding_box.max.y.ceil() as i32) {
-            for x in (bounding_box.min.x.floor() as i32)..=(bounding_box.max.x.ceil() as i32) {
-                let coord = Coord{x: x as f32, y: y as f32};
-                if let Some(interp) = polygon.test_inside(&coord) {
-                    // Interpolate colour.
-                    let shaded_colours = polygon.colours.map(|colours| {
-                        Colour {
-                            r: interpolate(&colours.iter().map(|c| c.r as f32).collect::<Vec<_>>(), &interp) as u8,
-                            g: interpolate(&colours.iter().map(|c| c.g as f32).collect::<Vec<_>>(), &interp) as u8,
-                            b: interpolate(&colours.iter().map(|c| c.b as f32).collect::<Vec<_>>(), &interp) as u8,
-                        }
-                    });
-                    let tex_colours = polygon.tex_coords.map(|tex_coords| {
-                        let x = interpolate(&tex_coords.iter().map(|c| c.x as f32).collect::<Vec<_>>(), &interp);
-                        let y = interpolate(&tex_coords.iter().map(|c| c.y as f32).collect::<Vec<_>>(), &interp);
-                        let tex_x = (x as usize) % texture.x;
-                        let tex_y = (y as usize) % texture.y;
-                        let index = (tex_y * texture.x) + tex_x;
-                        texture.colours[index]
-                    });
-                    let blended_colour = match (shaded_colours, tex_colours) {
-                        (None, None) => Colour::black(),
-                        (Some(c), None) => c,
-                        (None, Some(c)) => c,
-                        (Some(a), Some(b)) => a.blend(&b),
-                    };
-                    let idx = ((y * 256 + x) * 4) as usize;
-                    out[idx] = blended_colour.r;
-                    out[idx+1] = blended_colour.g;
-                    out[idx+2] = blended_colour.b;
+        let x_min = i32::max(bounding_box.min.x.floor() as i32, 0);
+        let x_max = i32::min(bounding_box.max.x.ceil() as i32, 255);
+        let y_min = i32::max(bounding_box.min.y.floor() as i32, 0);
+        let y_max = i32::min(bounding_box.max.y.ceil() as i32, 255);
+        if x_min > x_max || y_min > y_max {
+            continue;
+        }
+        rasterise_triangle(out, &mut depth_buffer, polygon, texture, sampler, shading, 256, x_min..=x_max, y_min..=y_max, 0);
+    }
+}
+
+/// Height in pixels of a row-band (the unit of rayon parallelism)
+/// and width in pixels of the column tiles used to cull polygons
+/// within a band, for `rasterise_parallel`.
+const TILE_SIZE: usize = 32;
+
+/// Rasterise to a `surface_size`x`surface_size` surface, splitting
+/// the work across rayon's thread pool.
+///
+/// The actual unit of parallelism is a horizontal band of
+/// `TILE_SIZE` rows: bands are disjoint mutable slices of `out`, so
+/// they can be handed to separate threads without locking. A true
+/// 2D tile (`TILE_SIZE`x`TILE_SIZE`) isn't a contiguous region of
+/// `out` — its rows are `surface_size * 4` bytes apart — so it can't be
+/// sliced out safely the same way; instead, within each band the
+/// polygons are culled serially against `TILE_SIZE`-wide column
+/// tiles purely to narrow the scan, not to add another layer of
+/// threading.
+///
+/// For small polygon counts the fixed cost of splitting bands and
+/// culling tiles dominates, so `rasterise` remains the better
+/// choice; this entry point is for large meshes.
+pub fn rasterise_parallel(out: &mut [u8], polygons: &[Polygon], texture: &Texture, sampler: &Sampler, shading: &Shading, surface_size: usize) {
+    use rayon::prelude::*;
+
+    let row_bytes = surface_size * 4;
+    let band_bytes = TILE_SIZE * row_bytes;
+
+    out.par_chunks_mut(band_bytes).enumerate().for_each(|(band_idx, band)| {
+        let y_start = band_idx * TILE_SIZE;
+        let band_rows = band.len() / row_bytes;
+        let y_end = y_start + band_rows - 1;
+        let mut depth_buffer = vec![f32::INFINITY; surface_size * band_rows];
+
+        for tile_x in (0..surface_size).step_by(TILE_SIZE) {
+            let tile_x_min = tile_x as f32;
+            let tile_x_max = (tile_x + TILE_SIZE - 1) as f32;
+
+            for polygon in polygons {
+                let bounding_box = polygon.bounding_box();
+                if bounding_box.max.x < tile_x_min || bounding_box.min.x > tile_x_max
+                    || (bounding_box.max.y as i32) < y_start as i32 || (bounding_box.min.y as i32) > y_end as i32 {
+                    continue;
+                }
+
+                let x_min = i32::max(bounding_box.min.x.floor() as i32, tile_x as i32);
+                let x_max = i32::min(bounding_box.max.x.ceil() as i32, (tile_x + TILE_SIZE - 1) as i32);
+                let y_min = i32::max(bounding_box.min.y.floor() as i32, y_start as i32);
+                let y_max = i32::min(bounding_box.max.y.ceil() as i32, y_end as i32);
+                if x_min > x_max || y_min > y_max {
+                    continue;
                 }
+
+                rasterise_triangle(band, &mut depth_buffer, polygon, texture, sampler, shading, surface_size, x_min..=x_max, y_min..=y_max, y_start as i32);
             }
         }
-    }
+    });
+}
+
+/// Texel filtering mode, analogous to `wgpu::SamplerDescriptor`'s
+/// `mag_filter`/`min_filter`.
+pub enum Filter {
+    Nearest,
+    Bilinear
+}
+
+/// Out-of-bounds addressing mode, analogous to
+/// `wgpu::SamplerDescriptor`'s `address_mode_*`.
+pub enum Wrap {
+    Repeat,
+    ClampToEdge,
+    Mirror
+}
+
+pub struct Sampler {
+    pub filter: Filter,
+    pub wrap: Wrap
 }
 
 pub struct Texture {
@@ -148,9 +397,51 @@ pub struct Texture {
 }
 
 impl Texture {
+    fn wrap_index(coord: i32, size: usize, wrap: &Wrap) -> usize {
+        match wrap {
+            Wrap::Repeat => coord.rem_euclid(size as i32) as usize,
+            Wrap::ClampToEdge => coord.clamp(0, size as i32 - 1) as usize,
+            Wrap::Mirror => {
+                let period = size as i32 * 2;
+                let m = coord.rem_euclid(period);
+                if m < size as i32 {
+                    m as usize
+                } else {
+                    (period - 1 - m) as usize
+                }
+            }
+        }
+    }
+
+    fn texel(&self, x: i32, y: i32, wrap: &Wrap) -> Colour {
+        let tex_x = Self::wrap_index(x, self.x, wrap);
+        let tex_y = Self::wrap_index(y, self.y, wrap);
+        self.colours[(tex_y * self.x) + tex_x]
+    }
+
+    /// Sample the texture at texel coordinates `(u, v)` using `sampler`'s
+    /// filter and wrap mode.
+    pub fn sample(&self, u: f32, v: f32, sampler: &Sampler) -> Colour {
+        match sampler.filter {
+            Filter::Nearest => self.texel(u.floor() as i32, v.floor() as i32, &sampler.wrap),
+            Filter::Bilinear => {
+                let x0 = u.floor();
+                let y0 = v.floor();
+                let fx = u - x0;
+                let fy = v - y0;
+                let x0 = x0 as i32;
+                let y0 = y0 as i32;
+
+                let top = self.texel(x0, y0, &sampler.wrap).lerp(&self.texel(x0 + 1, y0, &sampler.wrap), fx);
+                let bottom = self.texel(x0, y0 + 1, &sampler.wrap).lerp(&self.texel(x0 + 1, y0 + 1, &sampler.wrap), fx);
+                top.lerp(&bottom, fy)
+            }
+        }
+    }
+
     pub fn checkerboard() -> Self {
         Self {
-            x: 32, 
+            x: 32,
             y: 32,
             colours: (0..1024).map(|pos| {
                 let x_quad = (pos % 32) / 4;
@@ -163,4 +454,73 @@ impl Texture {
             }).collect::<Vec<_>>()
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolate_perspective_at_a_vertex_returns_that_vertex_value() {
+        let vals = [1.0, 2.0, 3.0];
+        let inv_w = [1.0, 0.5, 0.25];
+        let weights = [1.0, 0.0, 0.0];
+        let invw = interpolate(&inv_w, &weights);
+        assert_eq!(interpolate_perspective(&vals, &inv_w, &weights, invw), 1.0);
+    }
+
+    #[test]
+    fn interpolate_perspective_matches_linear_when_w_is_uniform() {
+        // When every vertex shares the same w, perspective-correct
+        // interpolation degenerates to plain barycentric interpolation.
+        let vals = [0.0, 10.0, 20.0];
+        let inv_w = [2.0, 2.0, 2.0];
+        let weights = [0.2, 0.3, 0.5];
+        let invw = interpolate(&inv_w, &weights);
+        let got = interpolate_perspective(&vals, &inv_w, &weights, invw);
+        let want = interpolate(&vals, &weights);
+        assert!((got - want).abs() < 1e-5);
+    }
+
+    fn single_colour_texture(colour: Colour) -> Texture {
+        Texture { x: 2, y: 2, colours: vec![colour; 4] }
+    }
+
+    #[test]
+    fn nearest_sample_picks_the_containing_texel() {
+        let texture = Texture {
+            x: 2,
+            y: 2,
+            colours: vec![Colour::black(), Colour::white(), Colour::white(), Colour::black()]
+        };
+        let sampler = Sampler { filter: Filter::Nearest, wrap: Wrap::ClampToEdge };
+        let sample = texture.sample(0.5, 0.5, &sampler);
+        assert_eq!((sample.r, sample.g, sample.b), (0, 0, 0));
+    }
+
+    #[test]
+    fn bilinear_sample_of_a_flat_texture_is_unchanged() {
+        let texture = single_colour_texture(Colour { r: 100, g: 150, b: 200 });
+        let sampler = Sampler { filter: Filter::Bilinear, wrap: Wrap::ClampToEdge };
+        let sample = texture.sample(1.0, 1.0, &sampler);
+        assert_eq!((sample.r, sample.g, sample.b), (100, 150, 200));
+    }
+
+    #[test]
+    fn wrap_repeat_cycles_coordinates() {
+        assert_eq!(Texture::wrap_index(-1, 4, &Wrap::Repeat), 3);
+        assert_eq!(Texture::wrap_index(4, 4, &Wrap::Repeat), 0);
+    }
+
+    #[test]
+    fn wrap_clamp_to_edge_saturates() {
+        assert_eq!(Texture::wrap_index(-5, 4, &Wrap::ClampToEdge), 0);
+        assert_eq!(Texture::wrap_index(5, 4, &Wrap::ClampToEdge), 3);
+    }
+
+    #[test]
+    fn wrap_mirror_reflects_past_the_edge() {
+        assert_eq!(Texture::wrap_index(-1, 4, &Wrap::Mirror), 0);
+        assert_eq!(Texture::wrap_index(4, 4, &Wrap::Mirror), 3);
+    }
+}
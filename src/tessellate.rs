@@ -0,0 +1,147 @@
+use crate::raster::{Coord, Coord3, Polygon};
+
+/// Find cross product of the vectors a->b and a->c, using the same
+/// sign convention as `raster::edge_function`: positive means `c`
+/// is on the "inside" side of the line from `a` to `b`, and the
+/// magnitude is twice the area of the triangle `a, b, c`.
+fn edge_function(a: Coord, b: Coord, c: Coord) -> f32 {
+    let x = (c.x - a.x) * (b.y - a.y);
+    let y = (c.y - a.y) * (b.x - a.x);
+    x - y
+}
+
+/// Signed area of a simple polygon, fanned out from its first
+/// vertex. Positive when the polygon winds in the direction
+/// `edge_function` treats as "inside".
+fn signed_area(path: &[Coord]) -> f32 {
+    let mut area = 0.0;
+    let v0 = path[0];
+    for i in 1..path.len() - 1 {
+        area += edge_function(v0, path[i], path[i + 1]);
+    }
+    area
+}
+
+fn to_triangle(a: Coord, b: Coord, c: Coord) -> Polygon {
+    Polygon {
+        vertices: [Coord3::screen(a.x, a.y), Coord3::screen(b.x, b.y), Coord3::screen(c.x, c.y)],
+        colours: None,
+        tex_coords: None,
+        normals: None
+    }
+}
+
+/// Whether `p` lies strictly inside the triangle `a, b, c`, excluding
+/// its edges and vertices.
+///
+/// Unlike `Polygon::test_inside` (which treats the boundary as
+/// "inside" so rasterisation doesn't leave gaps between adjacent
+/// triangles), ear-clipping needs to know whether some *other*
+/// polygon vertex blocks a candidate ear. A vertex that merely sits
+/// on the ear's edge — common for axis-aligned shapes or flattened
+/// curves with collinear points — doesn't block it.
+fn strictly_inside(a: Coord, b: Coord, c: Coord, p: Coord) -> bool {
+    let d0 = edge_function(a, b, p);
+    let d1 = edge_function(b, c, p);
+    let d2 = edge_function(c, a, p);
+    (d0 > 0.0 && d1 > 0.0 && d2 > 0.0) || (d0 < 0.0 && d1 < 0.0 && d2 < 0.0)
+}
+
+/// Whether vertex `i` of `path` is a convex "ear" that can be cut
+/// off without containing any other vertex of the polygon.
+fn is_ear(path: &[Coord], i: usize) -> bool {
+    let n = path.len();
+    let prev = path[(i + n - 1) % n];
+    let curr = path[i];
+    let next = path[(i + 1) % n];
+
+    // Reflex, or a degenerate/collinear triple: not a valid ear.
+    if edge_function(prev, curr, next) <= 0.0 {
+        return false;
+    }
+
+    (0..n)
+        .filter(|&k| k != (i + n - 1) % n && k != i && k != (i + 1) % n)
+        .all(|k| !strictly_inside(prev, curr, next, path[k]))
+}
+
+/// Triangulate a closed 2D path (e.g. a glyph or SVG-like shape
+/// outline) into filled `Polygon`s using ear-clipping.
+pub fn tessellate(path: &[Coord]) -> Vec<Polygon> {
+    let mut remaining = path.to_vec();
+    if signed_area(&remaining) < 0.0 {
+        remaining.reverse();
+    }
+
+    let mut polygons = Vec::new();
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&i| is_ear(&remaining, i));
+
+        let i = match ear {
+            Some(i) => i,
+            // No convex ear left uncontested; the polygon is
+            // degenerate (self-intersecting or zero-area) so stop
+            // rather than looping forever.
+            None => break,
+        };
+
+        let prev = remaining[(i + n - 1) % n];
+        let curr = remaining[i];
+        let next = remaining[(i + 1) % n];
+        polygons.push(to_triangle(prev, curr, next));
+        remaining.remove(i);
+    }
+
+    if remaining.len() == 3 {
+        polygons.push(to_triangle(remaining[0], remaining[1], remaining[2]));
+    }
+
+    polygons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f32, y: f32) -> Coord {
+        Coord { x, y }
+    }
+
+    #[test]
+    fn tessellate_triangle_yields_itself() {
+        let path = [coord(0.0, 0.0), coord(4.0, 0.0), coord(0.0, 4.0)];
+        let polygons = tessellate(&path);
+        assert_eq!(polygons.len(), 1);
+    }
+
+    #[test]
+    fn tessellate_square_yields_two_triangles() {
+        let path = [coord(0.0, 0.0), coord(4.0, 0.0), coord(4.0, 4.0), coord(0.0, 4.0)];
+        let polygons = tessellate(&path);
+        assert_eq!(polygons.len(), 2);
+    }
+
+    #[test]
+    fn tessellate_handles_collinear_point_along_an_edge() {
+        // The point at (2.0, 0.0) is collinear with its neighbours on
+        // the bottom edge; a boundary-inclusive containment test would
+        // wrongly treat it as blocking every ear along that edge.
+        let path = [
+            coord(0.0, 0.0),
+            coord(2.0, 0.0),
+            coord(4.0, 0.0),
+            coord(4.0, 4.0),
+            coord(0.0, 4.0),
+        ];
+        let polygons = tessellate(&path);
+        assert_eq!(polygons.len(), 3);
+    }
+
+    #[test]
+    fn tessellate_accepts_clockwise_winding() {
+        let path = [coord(0.0, 0.0), coord(0.0, 4.0), coord(4.0, 4.0), coord(4.0, 0.0)];
+        let polygons = tessellate(&path);
+        assert_eq!(polygons.len(), 2);
+    }
+}